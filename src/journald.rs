@@ -0,0 +1,41 @@
+#[cfg(feature = "journald")]
+pub mod journald_impl {
+    use crate::tracing_setup::build_targets;
+    use crate::TracingConfig;
+    use tracing_subscriber::registry::Registry;
+    use tracing_subscriber::Layer;
+
+    /// Build the journald layer for the tracing pipeline, filtered by
+    /// [`TracingConfig::journald_filter`].
+    ///
+    /// `tracing_journald` already forwards span and event fields as structured journal fields
+    /// rather than flattening them into the message, so nothing extra is needed here beyond
+    /// wiring up the syslog identifier and the per-target filter.
+    ///
+    /// Returns `None` (after printing a warning) if `/run/systemd/journal/socket` isn't
+    /// reachable, e.g. on a non-systemd host, so the caller can fall back to console logging.
+    pub fn journald_layer(
+        config: &TracingConfig,
+    ) -> Option<Box<dyn Layer<Registry> + Send + Sync>> {
+        let layer = match tracing_journald::layer() {
+            Ok(layer) => layer,
+            Err(err) => {
+                eprintln!(
+                    "tracing-setup: journald socket unavailable ({err}), falling back to console logging"
+                );
+                return None;
+            }
+        };
+
+        let layer = match &config.syslog_identifier {
+            Some(identifier) => layer.with_syslog_identifier(identifier.clone()),
+            None => layer,
+        };
+
+        Some(
+            layer
+                .with_filter(build_targets(&config.journald_filter))
+                .boxed(),
+        )
+    }
+}
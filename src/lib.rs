@@ -1,9 +1,13 @@
 pub mod tracing_setup;
 
-pub(crate) mod jaeger;
+pub(crate) mod journald;
+pub(crate) mod otel;
+pub mod panic_hook;
+pub(crate) mod syslog_target;
 
+pub use panic_hook::{install_panic_hook, PanicHookGuard};
 pub use tracing_setup::*;
 
 // re-exports
 pub use tracing;
-pub use tracing_appender;
\ No newline at end of file
+pub use tracing_appender;
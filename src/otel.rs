@@ -0,0 +1,117 @@
+#[cfg(feature = "otel")]
+pub mod otel_impl {
+    use crate::tracing_setup::build_targets;
+    use crate::{TelemetryConfig, TelemetryExporter, TracingConfig};
+    use opentelemetry::sdk::{trace, Resource};
+    use opentelemetry::KeyValue;
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::Duration;
+    use tracing_subscriber::registry::Registry;
+    use tracing_subscriber::Layer;
+
+    const DEFAULT_JAEGER_AGENT_HOSTNAME: &str = "localhost";
+    const DEFAULT_JAEGER_AGENT_PORT: u16 = 6831;
+    const DEFAULT_OTLP_GRPC_ENDPOINT: &str = "http://localhost:4317";
+    const DEFAULT_OTLP_HTTP_ENDPOINT: &str = "http://localhost:4318";
+
+    /// Build the resource describing this process, combining
+    /// [`TelemetryConfig::service_name`] with its arbitrary
+    /// [`TelemetryConfig::resource_attributes`].
+    fn resource(config: &TelemetryConfig) -> Resource {
+        let mut kvs = vec![KeyValue::new("service.name", config.service_name.clone())];
+        kvs.extend(
+            config
+                .resource_attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        );
+        Resource::new(kvs)
+    }
+
+    /// Build the telemetry layer for the tracing pipeline, filtered by
+    /// [`TelemetryConfig::filter`]. The exporter backend is chosen by
+    /// [`TelemetryConfig::exporter`], and [`TelemetryConfig::endpoint`] is interpreted
+    /// according to that choice: a bare hostname for [`TelemetryExporter::JaegerAgent`], or a
+    /// full `http(s)://host:port` URL for the OTLP exporters.
+    ///
+    /// # Panics
+    ///
+    /// The OTLP exporters (`OtlpGrpc`, `OtlpHttp`) batch and export spans over a Tokio runtime,
+    /// so [`init_tracing`](crate::init_tracing) must be called from within one when using them,
+    /// or this function panics when it installs the batch exporter. `JaegerAgent` has no such
+    /// requirement - it's
+    /// fire-and-forget UDP, so it's exported synchronously instead.
+    pub fn telemetry_layer(config: &TracingConfig) -> Box<dyn Layer<Registry> + Send + Sync> {
+        let telemetry = config
+            .telemetry
+            .as_ref()
+            .expect("TracingMode::Telemetry requires TracingConfig::telemetry to be set");
+
+        let tracer = match telemetry.exporter {
+            TelemetryExporter::JaegerAgent => {
+                let hostname = telemetry
+                    .endpoint
+                    .as_deref()
+                    .unwrap_or(DEFAULT_JAEGER_AGENT_HOSTNAME);
+                let endpt = format!("{hostname}:{DEFAULT_JAEGER_AGENT_PORT}");
+                opentelemetry_jaeger::new_agent_pipeline()
+                    .with_service_name(telemetry.service_name.clone())
+                    .with_endpoint(endpt)
+                    .with_trace_config(trace::config().with_resource(resource(telemetry)))
+                    .install_simple()
+                    .unwrap()
+            }
+            TelemetryExporter::OtlpGrpc => {
+                let endpoint = telemetry
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OTLP_GRPC_ENDPOINT.to_string());
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(
+                        opentelemetry_otlp::new_exporter()
+                            .tonic()
+                            .with_endpoint(endpoint),
+                    )
+                    .with_trace_config(trace::config().with_resource(resource(telemetry)))
+                    .install_batch(opentelemetry::runtime::Tokio)
+                    .unwrap()
+            }
+            TelemetryExporter::OtlpHttp => {
+                let endpoint = telemetry
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OTLP_HTTP_ENDPOINT.to_string());
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(
+                        opentelemetry_otlp::new_exporter()
+                            .http()
+                            .with_endpoint(endpoint),
+                    )
+                    .with_trace_config(trace::config().with_resource(resource(telemetry)))
+                    .install_batch(opentelemetry::runtime::Tokio)
+                    .unwrap()
+            }
+        };
+
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(build_targets(&telemetry.filter))
+            .boxed()
+    }
+
+    /// Block until `endpoint` (`host:port`) accepts a TCP connection.
+    ///
+    /// Useful for local dev setups where the collector is started alongside the process
+    /// tracing to it. Controlled by [`TelemetryConfig::wait_for_endpoint`] - production
+    /// deployments generally shouldn't busy-wait on a collector that may never come up.
+    pub fn wait_for_collector(endpoint: &str) {
+        while TcpStream::connect(endpoint).is_err() {
+            println!("Waiting for telemetry collector at {endpoint} to start...");
+            thread::sleep(Duration::from_secs(1));
+        }
+        println!("Found running telemetry collector!");
+    }
+}
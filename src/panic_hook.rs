@@ -0,0 +1,54 @@
+use std::panic::PanicHookInfo;
+use std::sync::Arc;
+
+/// Restores the previously-installed panic hook when dropped.
+///
+/// Returned by [`install_panic_hook`] so callers (tests in particular) can install the
+/// tracing-backed hook for the duration of a scope and have the original hook restored
+/// afterwards, rather than leaving it installed for the rest of the process.
+pub struct PanicHookGuard {
+    previous: Option<Arc<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static>>,
+}
+
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            std::panic::set_hook(Box::new(move |info| previous(info)));
+        }
+    }
+}
+
+/// Install a panic hook that re-emits each panic as a `tracing::error!` event - capturing the
+/// panic message, its source location, and a backtrace - before delegating to whatever hook
+/// was previously installed.
+///
+/// Because the hook runs inside whatever span was active when the thread panicked, the event
+/// carries that span's full context, so the file/json/otel/journald/syslog outputs all
+/// capture the panic uniformly instead of the panic text only reaching raw stderr.
+pub fn install_panic_hook() -> PanicHookGuard {
+    let previous: Arc<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static> =
+        Arc::from(std::panic::take_hook());
+    let previous_for_hook = Arc::clone(&previous);
+
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| "unknown location".to_string());
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let backtrace = std::backtrace::Backtrace::force_capture();
+
+        tracing::error!(%location, %message, %backtrace, "a thread panicked");
+
+        previous_for_hook(info);
+    }));
+
+    PanicHookGuard {
+        previous: Some(previous),
+    }
+}
@@ -0,0 +1,175 @@
+#[cfg(feature = "syslog")]
+pub mod syslog_impl {
+    use crate::tracing_setup::{build_targets, SyslogConfig, SyslogFormat, SyslogTransport};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use syslog::{Formatter3164, Formatter5424, Logger, LoggerBackend, Severity};
+    use tracing::Level;
+    use tracing_subscriber::fmt::MakeWriter;
+    use tracing_subscriber::registry::Registry;
+    use tracing_subscriber::Layer;
+
+    /// The two syslog framings we support, unified behind one handle so the rest of the
+    /// pipeline doesn't need to care which one is in use.
+    enum SyslogHandle {
+        Rfc3164(Logger<LoggerBackend, Formatter3164>),
+        Rfc5424(Logger<LoggerBackend, Formatter5424>),
+    }
+
+    impl SyslogHandle {
+        fn connect(config: &SyslogConfig) -> io::Result<Self> {
+            let handle = match config.format {
+                SyslogFormat::Rfc3164 => {
+                    let formatter = Formatter3164 {
+                        facility: config.facility,
+                        hostname: None,
+                        process: config.app_name.clone(),
+                        pid: std::process::id(),
+                    };
+                    SyslogHandle::Rfc3164(open(&config.transport, formatter)?)
+                }
+                SyslogFormat::Rfc5424 => {
+                    let formatter = Formatter5424 {
+                        facility: config.facility,
+                        hostname: None,
+                        process: config.app_name.clone(),
+                        pid: std::process::id(),
+                        ..Default::default()
+                    };
+                    SyslogHandle::Rfc5424(open(&config.transport, formatter)?)
+                }
+            };
+            Ok(handle)
+        }
+
+        fn send(&mut self, severity: Severity, message: &str) -> io::Result<()> {
+            let result = match self {
+                SyslogHandle::Rfc3164(logger) => emit(logger, severity, message),
+                SyslogHandle::Rfc5424(logger) => emit(logger, severity, message),
+            };
+            result.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))
+        }
+    }
+
+    fn open<F>(transport: &SyslogTransport, formatter: F) -> io::Result<Logger<LoggerBackend, F>>
+    where
+        F: Clone + syslog::LogFormat<String>,
+    {
+        let result = match transport {
+            SyslogTransport::Unix => syslog::unix(formatter),
+            SyslogTransport::UnixSocket(path) => syslog::unix_custom(formatter, path),
+            SyslogTransport::Udp { local, server } => syslog::udp(formatter, local, server),
+            SyslogTransport::Tcp { server } => syslog::tcp(formatter, server),
+        };
+        result.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))
+    }
+
+    /// Map a tracing [`Level`] onto the syslog severity it corresponds to.
+    fn level_to_severity(level: &Level) -> Severity {
+        match *level {
+            Level::ERROR => Severity::LOG_ERR,
+            Level::WARN => Severity::LOG_WARNING,
+            Level::INFO => Severity::LOG_INFO,
+            Level::DEBUG | Level::TRACE => Severity::LOG_DEBUG,
+        }
+    }
+
+    fn emit<F>(
+        logger: &mut Logger<LoggerBackend, F>,
+        severity: Severity,
+        message: &str,
+    ) -> Result<(), syslog::Error>
+    where
+        F: syslog::LogFormat<String>,
+    {
+        match severity {
+            Severity::LOG_EMERG => logger.emerg(message.to_string()),
+            Severity::LOG_ALERT => logger.alert(message.to_string()),
+            Severity::LOG_CRIT => logger.crit(message.to_string()),
+            Severity::LOG_ERR => logger.err(message.to_string()),
+            Severity::LOG_WARNING => logger.warning(message.to_string()),
+            Severity::LOG_NOTICE => logger.notice(message.to_string()),
+            Severity::LOG_INFO => logger.info(message.to_string()),
+            Severity::LOG_DEBUG => logger.debug(message.to_string()),
+        }
+    }
+
+    /// [`std::io::Write`] adapter that forwards each write to the syslog handle at whatever
+    /// severity [`SyslogMakeWriter::make_writer_for`] picked for this event.
+    struct SyslogWriter {
+        handle: Arc<Mutex<SyslogHandle>>,
+        severity: Severity,
+    }
+
+    impl io::Write for SyslogWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let message = String::from_utf8_lossy(buf);
+            self.handle
+                .lock()
+                .unwrap()
+                .send(self.severity, message.trim_end_matches('\n'))?;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// [`MakeWriter`] that picks the syslog severity from each event's [`tracing::Level`]
+    /// before handing back a writer for it.
+    struct SyslogMakeWriter(Arc<Mutex<SyslogHandle>>);
+
+    impl<'a> MakeWriter<'a> for SyslogMakeWriter {
+        type Writer = SyslogWriter;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            SyslogWriter {
+                handle: self.0.clone(),
+                severity: Severity::LOG_INFO,
+            }
+        }
+
+        fn make_writer_for(&'a self, meta: &tracing::Metadata<'_>) -> Self::Writer {
+            SyslogWriter {
+                handle: self.0.clone(),
+                severity: level_to_severity(meta.level()),
+            }
+        }
+    }
+
+    /// Build the syslog output layer, filtered by [`SyslogConfig::filter`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configured transport can't be reached, since there's no console fallback
+    /// to wire up: the caller asked for syslog specifically.
+    pub fn syslog_layer(config: &SyslogConfig) -> Box<dyn Layer<Registry> + Send + Sync> {
+        let handle = SyslogHandle::connect(config).expect("Failed to connect to syslog");
+        let writer = SyslogMakeWriter(Arc::new(Mutex::new(handle)));
+
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .without_time()
+            .with_target(false)
+            // unlike the console/file layers, syslog has no ansi_* knob of its own: the
+            // syslog daemon is never a tty, so ANSI escapes would just corrupt the record
+            .with_ansi(false)
+            .with_filter(build_targets(&config.filter))
+            .boxed()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn level_to_severity_maps_each_tracing_level() {
+            assert_eq!(level_to_severity(&Level::ERROR), Severity::LOG_ERR);
+            assert_eq!(level_to_severity(&Level::WARN), Severity::LOG_WARNING);
+            assert_eq!(level_to_severity(&Level::INFO), Severity::LOG_INFO);
+            assert_eq!(level_to_severity(&Level::DEBUG), Severity::LOG_DEBUG);
+            assert_eq!(level_to_severity(&Level::TRACE), Severity::LOG_DEBUG);
+        }
+    }
+}
@@ -1,13 +1,21 @@
 use chrono::Local;
 use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
 use tracing::subscriber::set_global_default;
 use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
     fmt::{self, format::FmtSpan},
-    EnvFilter, FmtSubscriber,
+    layer::{Layer, SubscriberExt},
+    registry::Registry,
 };
 pub const DEFAULT_SPAN_EVENTS: FmtSpan = FmtSpan::CLOSE;
 
+/// A single boxed layer in the tracing pipeline, already filtered for its target.
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 /// Controls how tracing is configured at a high level
 pub enum TracingMode {
@@ -18,9 +26,201 @@ pub enum TracingMode {
     File,
     /// Log to both the console and a file
     ConsoleAndFile,
-    /// Log to a live Jaeger instance. This automatically logs to the console as well
-    #[cfg(feature = "jaeger")]
-    JaegerLive,
+    /// Log to an OpenTelemetry-compatible backend. This automatically logs to the console as
+    /// well. See [`TracingConfig::telemetry`] for exporter selection.
+    #[cfg(feature = "otel")]
+    Telemetry,
+    /// Log to systemd-journald only
+    #[cfg(feature = "journald")]
+    Journald,
+    /// Log to both the console and journald
+    #[cfg(feature = "journald")]
+    ConsoleAndJournald,
+    /// Log to both a file and journald
+    #[cfg(feature = "journald")]
+    FileAndJournald,
+    /// Log to a syslog daemon only
+    #[cfg(feature = "syslog")]
+    Syslog,
+    /// Log to both the console and syslog
+    #[cfg(feature = "syslog")]
+    ConsoleAndSyslog,
+    /// Log to both a file and syslog
+    #[cfg(feature = "syslog")]
+    FileAndSyslog,
+}
+
+/// Which syslog transport to connect over.
+///
+/// See the [`syslog`](https://docs.rs/syslog) crate's `unix`/`udp`/`tcp` constructors.
+#[cfg(feature = "syslog")]
+#[derive(Debug, Clone)]
+pub enum SyslogTransport {
+    /// Connect to the local syslog daemon over its default Unix domain socket.
+    Unix,
+    /// Connect to a non-default Unix domain socket path.
+    UnixSocket(std::path::PathBuf),
+    /// Send datagrams over UDP, binding `local` before sending to `server`.
+    Udp {
+        local: std::net::SocketAddr,
+        server: std::net::SocketAddr,
+    },
+    /// Connect to a syslog daemon over TCP.
+    Tcp { server: std::net::SocketAddr },
+}
+
+/// Which syslog message framing to emit.
+#[cfg(feature = "syslog")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFormat {
+    /// RFC 3164 ("BSD syslog") framing.
+    Rfc3164,
+    /// RFC 5424 structured-data framing.
+    Rfc5424,
+}
+
+/// Which OpenTelemetry exporter backend to send spans to.
+#[cfg(feature = "otel")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryExporter {
+    /// The legacy Jaeger agent UDP protocol, via `opentelemetry_jaeger`.
+    JaegerAgent,
+    /// OTLP over gRPC, via `opentelemetry_otlp`'s `tonic` exporter.
+    OtlpGrpc,
+    /// OTLP over HTTP, via `opentelemetry_otlp`'s `http` exporter.
+    OtlpHttp,
+}
+
+/// Telemetry output sub-configuration. Only meaningful when the `otel` feature is enabled.
+#[cfg(feature = "otel")]
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// Which exporter backend to send spans to.
+    pub exporter: TelemetryExporter,
+
+    /// The service name reported in the exported resource attributes.
+    pub service_name: String,
+
+    /// Arbitrary additional resource attributes reported alongside `service.name`, e.g.
+    /// `("deployment.environment", "staging")`.
+    pub resource_attributes: Vec<(String, String)>,
+
+    /// Where to send spans. Interpreted according to [`Self::exporter`]: a bare hostname for
+    /// [`TelemetryExporter::JaegerAgent`] (agent port is fixed at `6831`), or a full
+    /// `http(s)://host:port` URL for the OTLP exporters. Defaults to each exporter's
+    /// conventional local address when unset.
+    pub endpoint: Option<String>,
+
+    /// Per-target filter for the telemetry layer. Same syntax as
+    /// [`TracingConfig::console_filter`].
+    pub filter: Option<String>,
+
+    /// If set, block on startup until this `host:port` accepts a TCP connection before
+    /// installing the telemetry layer. Intended for local dev setups where the collector is
+    /// started alongside the process tracing to it; leave unset in production, since a
+    /// collector that never comes up would hang the process forever.
+    pub wait_for_endpoint: Option<String>,
+}
+
+/// Which `tracing_subscriber::fmt` event style to render console/file output with.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// The default single-line, human-readable event format.
+    #[default]
+    Full,
+    /// A shorter variant of [`Self::Full`], optimized for short line lengths.
+    ///
+    /// See [`SubscriberBuilder::compact`](tracing_subscriber::fmt::SubscriberBuilder::compact).
+    Compact,
+    /// A more verbose, multi-line format intended for local development.
+    ///
+    /// See [`SubscriberBuilder::pretty`](tracing_subscriber::fmt::SubscriberBuilder::pretty).
+    Pretty,
+    /// Newline-delimited JSON.
+    ///
+    /// See [`SubscriberBuilder::json`](tracing_subscriber::fmt::SubscriberBuilder::json).
+    Json,
+    /// Newline-delimited JSON via [`tracing_bunyan_formatter`], which additionally captures
+    /// span-enter/exit durations and nested span context in a form log aggregators expect.
+    #[cfg(feature = "bunyan")]
+    Bunyan,
+}
+
+/// Error returned when parsing a [`LogFormat`] from an unrecognized string.
+#[derive(Debug)]
+pub struct ParseLogFormatError(String);
+
+impl std::fmt::Display for ParseLogFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unrecognized log format {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLogFormatError {}
+
+impl std::str::FromStr for LogFormat {
+    type Err = ParseLogFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "full" => Ok(LogFormat::Full),
+            "compact" => Ok(LogFormat::Compact),
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            #[cfg(feature = "bunyan")]
+            "bunyan" => Ok(LogFormat::Bunyan),
+            _ => Err(ParseLogFormatError(s.to_string())),
+        }
+    }
+}
+
+/// Which stream the console layer writes to.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleTarget {
+    /// Write to stdout, alongside normal program output.
+    #[default]
+    Stdout,
+    /// Write to stderr, so diagnostics stay separate from program output - the usual choice
+    /// for CLIs.
+    Stderr,
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for ConsoleTarget {
+    type Writer = Box<dyn Write + 'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match self {
+            ConsoleTarget::Stdout => Box::new(io::stdout()),
+            ConsoleTarget::Stderr => Box::new(io::stderr()),
+        }
+    }
+}
+
+/// How the file layer rotates its log file over time.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum FileRotation {
+    /// Never roll over; one file for the life of the process.
+    #[default]
+    Never,
+    /// Roll over to a new file every hour.
+    Hourly,
+    /// Roll over to a new file every day.
+    Daily,
+    /// Roll over to a new file once the current one exceeds `max_bytes`.
+    SizeBased { max_bytes: u64 },
+}
+
+/// Syslog output sub-configuration. Only meaningful when the `syslog` feature is enabled.
+#[cfg(feature = "syslog")]
+#[derive(Debug, Clone)]
+pub struct SyslogConfig {
+    pub transport: SyslogTransport,
+    pub format: SyslogFormat,
+    pub facility: syslog::Facility,
+    /// The app/process name reported to the syslog daemon.
+    pub app_name: String,
+    /// Per-target filter for the syslog layer. Same syntax as [`TracingConfig::console_filter`].
+    pub filter: Option<String>,
 }
 
 /// Holds information about how tracing should be configured
@@ -28,17 +228,28 @@ pub enum TracingMode {
 pub struct TracingConfig {
     pub tracing_mode: TracingMode,
 
-    /// Environment filter for tracing.
-    ///
-    /// See ['EnvFilter](tracing_subscriber::EnvFilter)
-    pub env_filter: Option<String>,
+    /// The fmt event style used for console and file output. Has no effect on telemetry export.
+    pub log_format: LogFormat,
 
-    /// Use JSON formatting for logs. This applies to all tracing modes except Jaeger.
-    pub json: bool,
+    /// Install a panic hook that re-emits panics as `tracing::error!` events, in addition to
+    /// delegating to the previously installed hook.
+    ///
+    /// See [`crate::panic_hook::install_panic_hook`].
+    pub capture_panics: bool,
 
     /// When writing to a file, this is the directory to write to.
     pub log_dir: String,
 
+    /// How the file layer rotates its log file. Defaults to [`FileRotation::Never`].
+    pub file_rotation: FileRotation,
+
+    /// Maximum number of rolled log files to retain in `log_dir`; the oldest ones beyond this
+    /// count are pruned after each rotation. `None` keeps everything.
+    ///
+    /// Has no effect when [`Self::file_rotation`] is [`FileRotation::Never`], since a file that
+    /// never rotates never produces anything to prune; a warning is printed in that case.
+    pub max_files: Option<usize>,
+
     /// ANSI colors in the file
     ///
     /// See [`SubscriberBuilder::with_ansi`](tracing_subscriber::fmt::SubscriberBuilder::with_ansi)
@@ -53,23 +264,61 @@ pub struct TracingConfig {
     /// See [`SubscriberBuilder::with_ansi`](tracing_subscriber::fmt::SubscriberBuilder::with_ansi)
     pub ansi_console: bool,
 
-    /// The hostname of the jaeger instance, if applicable
-    #[cfg(feature = "jaeger")]
-    pub jaeger_hostname: String,
+    /// Which stream the console layer writes to. Defaults to [`ConsoleTarget::Stdout`].
+    pub console_target: ConsoleTarget,
+
+    /// Per-target filter for the console layer.
+    ///
+    /// Comma-separated `target=level` directives plus an optional bare default level, e.g.
+    /// `"my_crate::module=trace,warn"`. Falls back to `RUST_LOG`, then `info`, when unset.
+    /// See [`Targets`](tracing_subscriber::filter::Targets).
+    pub console_filter: Option<String>,
+
+    /// Per-target filter for the file layer. Same syntax as [`Self::console_filter`].
+    pub file_filter: Option<String>,
+
+    /// Telemetry exporter/resource configuration. Required for [`TracingMode::Telemetry`].
+    #[cfg(feature = "otel")]
+    pub telemetry: Option<TelemetryConfig>,
+
+    /// Per-target filter for the journald layer. Same syntax as [`Self::console_filter`].
+    #[cfg(feature = "journald")]
+    pub journald_filter: Option<String>,
+
+    /// The syslog identifier journald should tag events with, so the service shows up
+    /// correctly in `journalctl -t <identifier>`. Defaults to the process name when unset.
+    #[cfg(feature = "journald")]
+    pub syslog_identifier: Option<String>,
+
+    /// Syslog transport/format/identity configuration. Required for the `Syslog`,
+    /// `ConsoleAndSyslog`, and `FileAndSyslog` tracing modes.
+    #[cfg(feature = "syslog")]
+    pub syslog: Option<SyslogConfig>,
 }
 
 impl Default for TracingConfig {
     fn default() -> Self {
         TracingConfig {
             tracing_mode: TracingMode::Console,
-            env_filter: None,
-            json: false,
+            log_format: LogFormat::Full,
+            capture_panics: false,
             log_dir: "./logs".to_string(),
+            file_rotation: FileRotation::Never,
+            max_files: None,
             ansi_file: false,
             lossy_file: true,
             ansi_console: true,
-            #[cfg(feature = "jaeger")]
-            jaeger_hostname: "localhost".to_string(),
+            console_target: ConsoleTarget::Stdout,
+            console_filter: None,
+            file_filter: None,
+            #[cfg(feature = "otel")]
+            telemetry: None,
+            #[cfg(feature = "journald")]
+            journald_filter: None,
+            #[cfg(feature = "journald")]
+            syslog_identifier: None,
+            #[cfg(feature = "syslog")]
+            syslog: None,
         }
     }
 }
@@ -80,154 +329,432 @@ impl Default for TracingConfig {
 /// This is specifically for writing to a file. When config.log_to_file is false, this will
 /// return None.
 pub fn init_tracing(config: TracingConfig) -> Option<WorkerGuard> {
-    // this separation is necessary because adding layers changes the type of the subscriber,
-    // so it's impossible to genericize and make this cleaner^
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    let mut guard = None;
+
     match config.tracing_mode {
-        TracingMode::Console => {
-            configure_console_logging(config);
-            None
+        TracingMode::Console => push_console_layer(&mut layers, &config),
+        TracingMode::File => guard = push_file_layer(&mut layers, &config, false),
+        TracingMode::ConsoleAndFile => {
+            push_console_layer(&mut layers, &config);
+            guard = push_file_layer(&mut layers, &config, true);
         }
-        TracingMode::File => configure_file_logging(config),
-        TracingMode::ConsoleAndFile => configure_combined_logging(config),
-        #[cfg(feature = "jaeger")]
-        TracingMode::JaegerLive => {
-            #[cfg(feature = "jaeger")]
+        #[cfg(feature = "otel")]
+        TracingMode::Telemetry => {
+            use crate::otel::otel_impl::*;
+            if let Some(endpoint) = config
+                .telemetry
+                .as_ref()
+                .and_then(|telemetry| telemetry.wait_for_endpoint.as_deref())
             {
-                use crate::jaeger::jaeger_impl::*;
-                wait_for_jaeger(&config.jaeger_hostname); // block until jaeger is running
-                println!("Initializing tracing for live streaming to Jaeger. Make sure you start the Jaeger Docker container.");
-                init_jaeger(config);
+                wait_for_collector(endpoint); // block until the collector is reachable
             }
-            #[cfg(not(feature = "jaeger"))]
-            {
-                println!("Jaeger tracing is not enabled. Please enable the 'jaeger' feature. The app will still run, but you won't see any output");
-            }
-
-            None
+            push_console_layer(&mut layers, &config);
+            layers.push(telemetry_layer(&config));
+        }
+        #[cfg(feature = "journald")]
+        TracingMode::Journald => push_journald(&mut layers, &config, false),
+        #[cfg(feature = "journald")]
+        TracingMode::ConsoleAndJournald => {
+            push_console_layer(&mut layers, &config);
+            push_journald(&mut layers, &config, true);
+        }
+        #[cfg(feature = "journald")]
+        TracingMode::FileAndJournald => {
+            guard = push_file_layer(&mut layers, &config, false);
+            push_journald(&mut layers, &config, guard.is_none());
+        }
+        #[cfg(feature = "syslog")]
+        TracingMode::Syslog => layers.push(syslog_layer(&config)),
+        #[cfg(feature = "syslog")]
+        TracingMode::ConsoleAndSyslog => {
+            push_console_layer(&mut layers, &config);
+            layers.push(syslog_layer(&config));
+        }
+        #[cfg(feature = "syslog")]
+        TracingMode::FileAndSyslog => {
+            guard = push_file_layer(&mut layers, &config, false);
+            layers.push(syslog_layer(&config));
         }
     }
-}
 
-/// Return an environment filter based on the provided config.
-/// If config.env_filter is None, the default filter will be used
-/// See [`EnvFilter::from_default_env`](tracing_subscriber::EnvFilter::from_default_env)
-pub fn env_filter(config: &TracingConfig) -> String {
-    if let Some(filter) = &config.env_filter {
-        filter.clone()
-    } else {
-        EnvFilter::from_default_env().to_string()
+    // every layer already carries its own filter, so the registry itself stays unfiltered
+    let subscriber = tracing_subscriber::registry().with(layers);
+    set_global_default(subscriber).expect("Failed to set global default");
+
+    if config.capture_panics {
+        // the hook should live for the rest of the process, same as the subscriber above;
+        // callers that need scoped install/teardown (e.g. tests) should call
+        // `install_panic_hook` directly and keep the guard instead of going through here.
+        std::mem::forget(crate::panic_hook::install_panic_hook());
     }
+
+    guard
 }
 
-/// Only file logging will be configured
-fn configure_file_logging(config: TracingConfig) -> Option<WorkerGuard> {
-    let (non_blocking, guard) = file_writer(&config);
+/// Build the per-target [`Targets`] filter for a single output layer.
+///
+/// If `filter` is `None`, falls back to the `RUST_LOG` environment variable, and finally to
+/// `info` if that isn't set either. An unparseable filter string also falls back to `info`.
+pub(crate) fn build_targets(filter: &Option<String>) -> Targets {
+    let spec = filter
+        .clone()
+        .or_else(|| std::env::var("RUST_LOG").ok())
+        .unwrap_or_else(|| "info".to_string());
 
-    let subscriber_builder = FmtSubscriber::builder()
-        .with_env_filter(env_filter(&config))
-        .with_span_events(DEFAULT_SPAN_EVENTS)
-        .with_ansi(config.ansi_file)
-        .with_writer(non_blocking);
+    spec.parse().unwrap_or_else(|err| {
+        eprintln!("tracing-setup: invalid filter {spec:?} ({err}), falling back to info");
+        Targets::new().with_default(LevelFilter::INFO)
+    })
+}
 
-    // since adding json formatting changes the type, some code needs to be duplicated
-    if config.json {
-        let subscriber = subscriber_builder.json().finish();
-        set_global_default(subscriber).expect("Failed to set global default");
-    } else {
-        let subscriber = subscriber_builder.finish();
-        set_global_default(subscriber).expect("Failed to set global default");
+/// Add the journald layer, or fall back to console logging if journald is unreachable.
+///
+/// `console_already_added` avoids pushing a duplicate console layer when the caller's mode
+/// already includes one (e.g. [`TracingMode::ConsoleAndJournald`]).
+#[cfg(feature = "journald")]
+fn push_journald(
+    layers: &mut Vec<BoxedLayer>,
+    config: &TracingConfig,
+    console_already_added: bool,
+) {
+    use crate::journald::journald_impl::journald_layer;
+
+    match journald_layer(config) {
+        Some(layer) => layers.push(layer),
+        None if !console_already_added => push_console_layer(layers, config),
+        None => {}
     }
+}
 
-    Some(guard)
+/// Build the syslog output layer from [`TracingConfig::syslog`].
+///
+/// # Panics
+///
+/// Panics if `config.syslog` is unset, or if the configured transport can't be reached.
+#[cfg(feature = "syslog")]
+fn syslog_layer(config: &TracingConfig) -> BoxedLayer {
+    use crate::syslog_target::syslog_impl::syslog_layer;
+
+    let syslog_config = config
+        .syslog
+        .as_ref()
+        .expect("TracingMode::Syslog requires TracingConfig::syslog to be set");
+    syslog_layer(syslog_config)
 }
 
-/// Only console logging will be configured
-fn configure_console_logging(config: TracingConfig) {
-    // let format = fmt::format().json();
-    let subscriber_builder = FmtSubscriber::builder()
-        .with_env_filter(env_filter(&config))
+/// Build and push the console output layer(s), applying [`TracingConfig::log_format`] and
+/// [`TracingConfig::console_filter`].
+///
+/// This pushes more than one layer for [`LogFormat::Bunyan`], which needs a separate
+/// `JsonStorageLayer` to accumulate span context ahead of its formatting layer.
+fn push_console_layer(layers: &mut Vec<BoxedLayer>, config: &TracingConfig) {
+    let filter = build_targets(&config.console_filter);
+
+    #[cfg(feature = "bunyan")]
+    if config.log_format == LogFormat::Bunyan {
+        return push_bunyan_layers(layers, config.console_target, filter);
+    }
+
+    let base = fmt::layer()
+        .with_writer(config.console_target)
         .with_span_events(DEFAULT_SPAN_EVENTS)
         .with_ansi(config.ansi_console);
 
-    // since adding json formatting changes the type, some code needs to be duplicated
-    if config.json {
-        let subscriber = subscriber_builder.json().finish();
-        set_global_default(subscriber).expect("Failed to set global default");
-    } else {
-        let subscriber = subscriber_builder.finish();
-        set_global_default(subscriber).expect("Failed to set global default");
-    }
-}
-
-/// Both console and file logging will be configured
-// reward for whoever can make this cleaner
-fn configure_combined_logging(config: TracingConfig) -> Option<WorkerGuard> {
-    use tracing_subscriber::prelude::*;
-    // File writer setup
-    let (non_blocking, guard) = file_writer(&config);
-
-    // due to some complexities in the type system, this code is duplicated
-    // the only difference is that when layers are added to the subscriber, if config.json
-    // json formatting is used.
-    if config.json {
-        let stdout_layer = fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_span_events(DEFAULT_SPAN_EVENTS)
-            .with_ansi(config.ansi_console);
-        let file_layer = fmt::layer()
-            .with_writer(non_blocking)
-            .with_span_events(DEFAULT_SPAN_EVENTS)
-            .with_ansi(config.ansi_file);
-        let subscriber = tracing_subscriber::registry()
-            .with(stdout_layer.json())
-            .with(file_layer.json())
-            .with(EnvFilter::new(env_filter(&config)));
-        set_global_default(subscriber).expect("Failed to set global default");
-    } else {
-        let stdout_layer = fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_span_events(DEFAULT_SPAN_EVENTS)
-            .with_ansi(config.ansi_console);
-        let file_layer = fmt::layer()
-            .with_writer(non_blocking)
-            .with_span_events(DEFAULT_SPAN_EVENTS)
-            .with_ansi(config.ansi_file);
-        let subscriber = tracing_subscriber::registry()
-            .with(stdout_layer)
-            .with(file_layer)
-            .with(EnvFilter::new(env_filter(&config)));
-        set_global_default(subscriber).expect("Failed to set global default");
+    match config.log_format {
+        LogFormat::Full => layers.push(base.with_filter(filter).boxed()),
+        LogFormat::Compact => layers.push(base.compact().with_filter(filter).boxed()),
+        LogFormat::Pretty => layers.push(base.pretty().with_filter(filter).boxed()),
+        LogFormat::Json => layers.push(base.json().with_filter(filter).boxed()),
+        #[cfg(feature = "bunyan")]
+        LogFormat::Bunyan => unreachable!("handled above"),
     }
+}
+
+/// Build and push the file output layer(s), applying [`TracingConfig::log_format`] and
+/// [`TracingConfig::file_filter`]. Returns the [`WorkerGuard`] that flushes pending writes.
+///
+/// If the log file can't be created, this is non-fatal: it emits a warning to
+/// [`TracingConfig::console_target`] and continues with console-only logging instead of
+/// panicking, returning `None`. `console_already_added` avoids pushing a duplicate console
+/// layer when the caller's mode already includes one.
+fn push_file_layer(
+    layers: &mut Vec<BoxedLayer>,
+    config: &TracingConfig,
+    console_already_added: bool,
+) -> Option<WorkerGuard> {
+    let (non_blocking, guard) = match file_writer(config) {
+        Ok(writer) => writer,
+        Err(err) => {
+            warn_to_console(
+                config,
+                &format!(
+                    "tracing-setup: failed to initialize file logging ({err}), falling back to console-only logging"
+                ),
+            );
+            if !console_already_added {
+                push_console_layer(layers, config);
+            }
+            return None;
+        }
+    };
+    let filter = build_targets(&config.file_filter);
+
+    #[cfg(feature = "bunyan")]
+    if config.log_format == LogFormat::Bunyan {
+        push_bunyan_layers(layers, non_blocking, filter);
+        return Some(guard);
+    }
+
+    let base = fmt::layer()
+        .with_writer(non_blocking)
+        .with_span_events(DEFAULT_SPAN_EVENTS)
+        .with_ansi(config.ansi_file);
+
+    match config.log_format {
+        LogFormat::Full => layers.push(base.with_filter(filter).boxed()),
+        LogFormat::Compact => layers.push(base.compact().with_filter(filter).boxed()),
+        LogFormat::Pretty => layers.push(base.pretty().with_filter(filter).boxed()),
+        LogFormat::Json => layers.push(base.json().with_filter(filter).boxed()),
+        #[cfg(feature = "bunyan")]
+        LogFormat::Bunyan => unreachable!("handled above"),
+    }
+
     Some(guard)
 }
 
+/// Print a warning to whichever stream [`TracingConfig::console_target`] selects, for
+/// diagnostics raised before the tracing subscriber itself is up.
+fn warn_to_console(config: &TracingConfig, message: &str) {
+    match config.console_target {
+        ConsoleTarget::Stdout => println!("{message}"),
+        ConsoleTarget::Stderr => eprintln!("{message}"),
+    }
+}
+
+/// Push a `JsonStorageLayer` + `BunyanFormattingLayer` pair onto `layers`, filtered by
+/// `filter`. The storage layer must come first so the formatting layer can see the span
+/// context it accumulates.
+#[cfg(feature = "bunyan")]
+fn push_bunyan_layers(
+    layers: &mut Vec<BoxedLayer>,
+    writer: impl for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+    filter: Targets,
+) {
+    use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+
+    layers.push(JsonStorageLayer.with_filter(filter.clone()).boxed());
+    layers.push(
+        BunyanFormattingLayer::new(process_name(), writer)
+            .with_filter(filter)
+            .boxed(),
+    );
+}
+
+/// The current executable's file name, used to identify this process in bunyan-formatted logs.
+#[cfg(feature = "bunyan")]
+fn process_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 /// Create a file writer based on a tracing config
 ///
-/// The file writer will log tracing information to a file
-fn file_writer(config: &TracingConfig) -> (NonBlocking, WorkerGuard) {
-    let filename = create_log_filename(&config.log_dir);
-    create_parent_directory(&filename).expect("Failed to create parent log directory");
-    let file_writer = File::create(&filename).expect("Failed to create log file");
-    NonBlockingBuilder::default()
-        .lossy(config.lossy_file)
-        .finish(file_writer)
-}
-
-/// Generate a log filename based on the current time
-fn create_log_filename(log_dir: &str) -> String {
+/// The file writer will log tracing information to a file, rolling over according to
+/// [`TracingConfig::file_rotation`] and pruning old files per [`TracingConfig::max_files`].
+///
+/// Returns an error instead of panicking if the log directory or file can't be created, so
+/// [`push_file_layer`] can fall back to console-only logging.
+fn file_writer(config: &TracingConfig) -> io::Result<(NonBlocking, WorkerGuard)> {
+    std::fs::create_dir_all(&config.log_dir)?;
+
+    match &config.file_rotation {
+        FileRotation::SizeBased { max_bytes } => {
+            let writer = SizeRotatingWriter::new(&config.log_dir, *max_bytes, config.max_files)?;
+            Ok(NonBlockingBuilder::default()
+                .lossy(config.lossy_file)
+                .finish(writer))
+        }
+        time_based => {
+            let rotation = match time_based {
+                FileRotation::Never => Rotation::NEVER,
+                FileRotation::Hourly => Rotation::HOURLY,
+                FileRotation::Daily => Rotation::DAILY,
+                FileRotation::SizeBased { .. } => unreachable!("handled above"),
+            };
+
+            let mut builder = RollingFileAppender::builder()
+                .rotation(rotation)
+                .filename_prefix("log")
+                .filename_suffix("txt");
+            if let Some(max_files) = config.max_files {
+                if time_based == &FileRotation::Never {
+                    warn_to_console(
+                        config,
+                        "tracing-setup: max_files has no effect with FileRotation::Never, since a file that never rotates has nothing to prune",
+                    );
+                } else {
+                    builder = builder.max_log_files(max_files);
+                }
+            }
+            let appender = builder
+                .build(&config.log_dir)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+            Ok(NonBlockingBuilder::default()
+                .lossy(config.lossy_file)
+                .finish(appender))
+        }
+    }
+}
+
+/// Generate a log filename based on the current time and a monotonic sequence number, for
+/// [`FileRotation::SizeBased`], which `tracing_appender::rolling` doesn't support natively.
+///
+/// The sequence number is required in addition to the timestamp: under a high-volume workload
+/// the non-blocking worker can roll several times within the same second, and a timestamp
+/// alone would make those rotations collide on one path, silently truncating the log written
+/// so far that second.
+fn create_log_filename(log_dir: &str, sequence: u64) -> String {
     let now = Local::now();
-    format!("{}/log_{}.txt", log_dir, now.format("%m-%d-%Y_%H-%M-%S"))
-}
-
-/// Given a path, create the parent directory if it doesn't exist.
-/// Otherwise, do nothing.
-fn create_parent_directory(path: &str) -> std::io::Result<()> {
-    let path = std::path::Path::new(path);
-    if let Some(parent) = path.parent() {
-        // create the parent directory if it doesn't exist
-        std::fs::create_dir_all(parent)
-    } else {
-        // no parent directory to create
+    format!(
+        "{}/log_{}_{sequence}.txt",
+        log_dir,
+        now.format("%m-%d-%Y_%H-%M-%S")
+    )
+}
+
+/// A [`Write`] implementation that rolls over to a new timestamped file once the current one
+/// exceeds `max_bytes`, pruning the oldest files in `log_dir` down to `max_files` afterward.
+struct SizeRotatingWriter {
+    log_dir: String,
+    max_bytes: u64,
+    max_files: Option<usize>,
+    current: File,
+    current_len: u64,
+    sequence: u64,
+}
+
+impl SizeRotatingWriter {
+    fn new(log_dir: &str, max_bytes: u64, max_files: Option<usize>) -> io::Result<Self> {
+        let sequence = 0;
+        let current = File::create(create_log_filename(log_dir, sequence))?;
+        Ok(Self {
+            log_dir: log_dir.to_string(),
+            max_bytes,
+            max_files,
+            current,
+            current_len: 0,
+            sequence,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.sequence += 1;
+        self.current = File::create(create_log_filename(&self.log_dir, self.sequence))?;
+        self.current_len = 0;
+        if let Some(max_files) = self.max_files {
+            prune_old_logs(Path::new(&self.log_dir), max_files)?;
+        }
         Ok(())
     }
 }
+
+impl Write for SizeRotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.current_len >= self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.current.write(buf)?;
+        self.current_len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.current.flush()
+    }
+}
+
+/// Returns whether `file_name` looks like one of the rolled log files
+/// [`create_log_filename`] produces, i.e. `log_<timestamp>_<sequence>.txt`.
+fn is_rolled_log_filename(file_name: &str) -> bool {
+    file_name.starts_with("log_") && file_name.ends_with(".txt")
+}
+
+/// Delete the oldest rolled log files in `log_dir` until at most `max_files` remain.
+///
+/// Only considers files matching [`is_rolled_log_filename`], so unrelated files a user keeps
+/// alongside the logs (a `.gitkeep`, a differently-named file) are never touched.
+fn prune_old_logs(log_dir: &Path, max_files: usize) -> io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(log_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter(|entry| is_rolled_log_filename(&entry.file_name().to_string_lossy()))
+        .collect();
+
+    if entries.len() <= max_files {
+        return Ok(());
+    }
+
+    entries.sort_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok());
+
+    for entry in entries.iter().take(entries.len() - max_files) {
+        let _ = std::fs::remove_file(entry.path());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn log_format_from_str_parses_known_values_case_insensitively() {
+        assert_eq!(LogFormat::from_str("full").unwrap(), LogFormat::Full);
+        assert_eq!(LogFormat::from_str("Compact").unwrap(), LogFormat::Compact);
+        assert_eq!(LogFormat::from_str("PRETTY").unwrap(), LogFormat::Pretty);
+        assert_eq!(LogFormat::from_str("json").unwrap(), LogFormat::Json);
+    }
+
+    #[test]
+    fn log_format_from_str_rejects_unrecognized_values() {
+        let err = LogFormat::from_str("xml").unwrap_err();
+        assert_eq!(err.to_string(), "unrecognized log format \"xml\"");
+    }
+
+    #[test]
+    fn prune_old_logs_keeps_the_newest_max_files() {
+        let dir =
+            std::env::temp_dir().join(format!("tracing-setup-prune-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Write the files oldest-first with a delay in between so their mtimes are strictly
+        // ordered, since that's what `prune_old_logs` sorts on.
+        let names = ["log_1.txt", "log_2.txt", "log_3.txt", "log_4.txt"];
+        for name in names {
+            std::fs::write(dir.join(name), b"x").unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        prune_old_logs(&dir, 2).unwrap();
+
+        let remaining: std::collections::HashSet<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains("log_3.txt"));
+        assert!(remaining.contains("log_4.txt"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}